@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use crate::{
+    dynamic_modint::{DynamicModInt, DynamicModulusFactory},
+    math::pow_mod,
+    num_integer::Integer,
+    num_number::FromNumber,
+    poly::Convolution,
+};
+
+/// Three fixed NTT-friendly primes (sharing primitive root 3) used to carry out a
+/// convolution modulo an arbitrary, possibly non-NTT-friendly, modulus via CRT.
+const NTT_PRIMES: [u64; 3] = [167772161, 469762049, 998244353];
+const NTT_ROOT: u64 = 3;
+
+fn ntt<T: Integer + FromNumber>(a: &mut [T], invert: bool, p: T) {
+    let n = a.len();
+    let mut j = 0usize;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+    let mut len = 2usize;
+    while len <= n {
+        let mut w = pow_mod(T::from(NTT_ROOT), (p - T::ONE) / T::from(len as u64), p);
+        if invert {
+            w = pow_mod(w, p - T::from(2), p);
+        }
+        let mut i = 0;
+        while i < n {
+            let mut wn = T::ONE;
+            for k in 0..len / 2 {
+                let u = a[i + k];
+                let v = T::mul_mod(a[i + k + len / 2], wn, p);
+                a[i + k] = if u + v >= p { u + v - p } else { u + v };
+                a[i + k + len / 2] = if u >= v { u - v } else { u + p - v };
+                wn = T::mul_mod(wn, w, p);
+            }
+            i += len;
+        }
+        len <<= 1;
+    }
+    if invert {
+        let n_inv = pow_mod(T::from(n as u64), p - T::from(2), p);
+        for x in a.iter_mut() {
+            *x = T::mul_mod(*x, n_inv, p);
+        }
+    }
+}
+
+fn convolution_mod<T: Integer + FromNumber>(a: &[T], b: &[T], p: T) -> Vec<T> {
+    let rank = a.len() + b.len() - 2;
+    let mut n = 1;
+    while n <= rank {
+        n <<= 1;
+    }
+    let mut fa = a.to_vec();
+    let mut fb = b.to_vec();
+    fa.resize(n, T::ZERO);
+    fb.resize(n, T::ZERO);
+    ntt(&mut fa, false, p);
+    ntt(&mut fb, false, p);
+    for i in 0..n {
+        fa[i] = T::mul_mod(fa[i], fb[i], p);
+    }
+    ntt(&mut fa, true, p);
+    fa.truncate(rank + 1);
+    fa
+}
+
+/// Convolution over an arbitrary modulus, including non-NTT-friendly ones.
+///
+/// Lifts both operands to plain integers, convolves them independently modulo three fixed
+/// NTT-friendly primes, reconstructs each coefficient via Garner's algorithm, and finally
+/// reduces it into the caller's modulus.
+///
+/// The reconstruction is exact only while every true coefficient, at most `n * (m - 1)^2`
+/// for `n` the shorter operand's length, stays below the primes' product (about 7.9e25),
+/// e.g. `m` near 1e9 with up to about 7e7 terms; beyond that the results are not correct.
+pub struct ConvAnyMod<T, F>(PhantomData<(T, F)>)
+where
+    T: 'static + Integer,
+    F: DynamicModulusFactory<T>;
+
+impl<T, F> Convolution<DynamicModInt<T, F>> for ConvAnyMod<T, F>
+where
+    T: 'static + Integer + FromNumber,
+    F: DynamicModulusFactory<T>,
+{
+    fn convolution(
+        a: Vec<DynamicModInt<T, F>>,
+        b: Vec<DynamicModInt<T, F>>,
+    ) -> Vec<DynamicModInt<T, F>> {
+        if a.is_empty() || b.is_empty() {
+            return Vec::new();
+        }
+        let ua: Vec<T> = a.iter().map(|x| x.value()).collect();
+        let ub: Vec<T> = b.iter().map(|x| x.value()).collect();
+
+        let [p1, p2, p3] = NTT_PRIMES.map(T::from);
+        let r1 = convolution_mod(&ua, &ub, p1);
+        let r2 = convolution_mod(&ua, &ub, p2);
+        let r3 = convolution_mod(&ua, &ub, p3);
+
+        let m = F::modulus().modulus;
+        let m1_inv_m2 = pow_mod(p1 % p2, p2 - T::from(2), p2);
+        let m1m2_mod_m3 = T::mul_mod(p1 % p3, p2 % p3, p3);
+        let m1m2_mod_m = T::mul_mod(p1 % m, p2 % m, m);
+        let m1m2_inv_m3 = pow_mod(m1m2_mod_m3, p3 - T::from(2), p3);
+
+        r1.into_iter()
+            .zip(r2)
+            .zip(r3)
+            .map(|((x1, x2), x3)| {
+                // Garner's algorithm: reconstruct x mod (p1 * p2 * p3), reducing
+                // into the needed target modulus (p3, then m) at every step so we
+                // never need an integer wider than the modulus itself.
+                let t1 = x1;
+                let t2 = T::mul_mod((x2 + p2 - t1 % p2) % p2, m1_inv_m2, p2);
+                let x12_mod_m = (t1 % m + T::mul_mod(p1 % m, t2 % m, m)) % m;
+                let x12_mod_p3 = (t1 % p3 + T::mul_mod(p1 % p3, t2 % p3, p3)) % p3;
+                let t3 = T::mul_mod((x3 + p3 - x12_mod_p3) % p3, m1m2_inv_m3, p3);
+                let x = (x12_mod_m + T::mul_mod(m1m2_mod_m, t3 % m, m)) % m;
+                DynamicModInt::new(x)
+            })
+            .collect()
+    }
+}
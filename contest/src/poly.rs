@@ -24,6 +24,11 @@ pub trait Convolution<T: Ring> {
     }
 }
 
+pub trait FieldSqrt: Field {
+    /// Returns a square root of `self`, or `None` if `self` is not a quadratic residue.
+    fn sqrt(&self) -> Option<Self>;
+}
+
 pub trait PolyInverse<T: Field + FromNumber>: Convolution<T> {
     fn inverse(a: Vec<T>, n: usize) -> Vec<T> {
         poly_trim(Self::inverse_internal(&poly_extend(a, n)[..]))
@@ -50,6 +55,70 @@ pub trait PolyInverse<T: Field + FromNumber>: Convolution<T> {
     }
 }
 
+/// A binary subproduct tree over `x - xs[i]`, built the same way `Poly::batch_mul` combines
+/// its leaves, reused by both `multipoint_evaluate` (descend, taking remainders) and
+/// `interpolate` (ascend, combining weighted terms).
+enum SubproductNode<T: Field + FromNumber, C: PolyInverse<T>> {
+    Leaf(Poly<T, C>),
+    Branch {
+        product: Poly<T, C>,
+        left: Box<SubproductNode<T, C>>,
+        right: Box<SubproductNode<T, C>>,
+    },
+}
+
+impl<T: Field + FromNumber, C: PolyInverse<T>> SubproductNode<T, C> {
+    fn build(xs: &[T]) -> Self {
+        if xs.len() == 1 {
+            return Self::Leaf(Poly::new(vec![T::zero() - xs[0], T::one()]));
+        }
+        let mid = xs.len() >> 1;
+        let (a, b) = xs.split_at(mid);
+        let left = Box::new(Self::build(a));
+        let right = Box::new(Self::build(b));
+        let product = left.product().clone() * right.product().clone();
+        Self::Branch { product, left, right }
+    }
+
+    fn product(&self) -> &Poly<T, C> {
+        match self {
+            Self::Leaf(p) => p,
+            Self::Branch { product, .. } => product,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Leaf(_) => 1,
+            Self::Branch { left, right, .. } => left.len() + right.len(),
+        }
+    }
+
+    fn evaluate(&self, p: &Poly<T, C>, out: &mut [T], offset: usize) {
+        match self {
+            Self::Leaf(_) => out[offset] = p.0[0],
+            Self::Branch { left, right, .. } => {
+                let lp = p.clone() % left.product().clone();
+                let rp = p.clone() % right.product().clone();
+                left.evaluate(&lp, out, offset);
+                right.evaluate(&rp, out, offset + left.len());
+            }
+        }
+    }
+
+    fn interpolate(&self, terms: &[T]) -> Poly<T, C> {
+        match self {
+            Self::Leaf(_) => Poly::with_constant(terms[0]),
+            Self::Branch { left, right, .. } => {
+                let (a, b) = terms.split_at(left.len());
+                let pl = left.interpolate(a);
+                let pr = right.interpolate(b);
+                pl * right.product().clone() + pr * left.product().clone()
+            }
+        }
+    }
+}
+
 #[derive(Eq)]
 pub struct Poly<T: Ring, C: Convolution<T>>(Vec<T>, PhantomData<(T, C)>);
 impl<T: Ring, C: Convolution<T>> PartialEq for Poly<T, C> {
@@ -135,6 +204,79 @@ impl<T: Field + FromNumber, C: PolyInverse<T>> Poly<T, C> {
         prod.reverse();
         Self::new(prod)
     }
+    /// Returns the k-th term (0-indexed) of the sequence defined by
+    /// `a_i = rec[0]*a_{i-1} + rec[1]*a_{i-2} + ... + rec[d-1]*a_{i-d}` for `i >= d`,
+    /// with `init` supplying `a_0, ..., a_{d-1}`.
+    pub fn kth_term_of_recurrence(rec: &[T], init: &[T], k: u64) -> T {
+        let d = rec.len();
+        should_eq!(init.len(), d);
+        let mut q = vec![T::zero(); d + 1];
+        q[0] = T::one();
+        for i in 0..d {
+            q[i + 1] = T::zero() - rec[i];
+        }
+        let q = Self::new(q);
+        let p = (Self::new(init.to_vec()) * q.clone()).modular(d);
+        Self::coefficient_of_rational(p, q, k)
+    }
+
+    /// Returns `[x^k] P(x) / Q(x)` via Bostan-Mori, assuming `Q(0) != 0`.
+    pub fn coefficient_of_rational(mut p: Self, mut q: Self, mut k: u64) -> T {
+        should!(q.0[0] != T::zero());
+        loop {
+            let mut q_neg = q.clone();
+            for (i, x) in q_neg.0.iter_mut().enumerate() {
+                if i % 2 == 1 {
+                    *x = T::zero() - *x;
+                }
+            }
+            let u = (p * q_neg.clone()).0;
+            let v = (q * q_neg).0;
+            p = if k % 2 == 0 {
+                Self::new(u.into_iter().step_by(2).collect())
+            } else {
+                Self::new(u.into_iter().skip(1).step_by(2).collect())
+            };
+            q = Self::new(v.into_iter().step_by(2).collect());
+            k /= 2;
+            if k == 0 {
+                return p.0[0] / q.0[0];
+            }
+        }
+    }
+
+    /// Evaluates `self` at every point in `xs` in O(n log^2 n) via the subproduct tree.
+    pub fn multipoint_evaluate(&self, xs: &[T]) -> Vec<T> {
+        if xs.is_empty() {
+            return Vec::new();
+        }
+        let tree = SubproductNode::build(xs);
+        self.multipoint_evaluate_with_tree(&tree, xs.len())
+    }
+
+    fn multipoint_evaluate_with_tree(&self, tree: &SubproductNode<T, C>, n: usize) -> Vec<T> {
+        let r = self.clone() % tree.product().clone();
+        let mut out = vec![T::zero(); n];
+        tree.evaluate(&r, &mut out, 0);
+        out
+    }
+
+    /// Returns the unique polynomial `p` of degree `< xs.len()` with `p(xs[i]) == ys[i]` for
+    /// every `i`, via the subproduct tree (Lagrange interpolation).
+    pub fn interpolate(xs: &[T], ys: &[T]) -> Self {
+        should_eq!(xs.len(), ys.len());
+        if xs.is_empty() {
+            return Self::zero();
+        }
+        let tree = SubproductNode::build(xs);
+        let weights = tree
+            .product()
+            .differential()
+            .multipoint_evaluate_with_tree(&tree, xs.len());
+        let terms: Vec<T> = ys.iter().zip(weights.iter()).map(|(&y, &w)| y / w).collect();
+        tree.interpolate(&terms)
+    }
+
     pub fn downgrade_mod(self: Self, mut n: impl Iterator<Item = usize>) -> Self {
         if self.rank() == 0 {
             return Self::zero();
@@ -175,6 +317,67 @@ impl<T: Field + FromNumber, C: PolyInverse<T>> Poly<T, C> {
     }
 }
 
+impl<T: FieldSqrt + FromNumber, C: PolyInverse<T>> Poly<T, C> {
+    /// Returns `g` with `g^2 ≡ self (mod x^n)`, or `None` if `self` has no such square root.
+    pub fn sqrt(self, n: usize) -> Option<Self> {
+        if n == 0 {
+            return Some(Self::zero());
+        }
+        let mut low = 0;
+        while low <= self.rank() && self.0[low] == T::zero() {
+            low += 1;
+        }
+        if low > self.rank() || low >= n {
+            return Some(Self::zero());
+        }
+        if low % 2 != 0 {
+            return None;
+        }
+        let half = low / 2;
+        let shifted = Self::new(self.0[low..].to_vec());
+        let r0 = shifted.0[0].sqrt()?;
+        let inv2 = Self::with_constant(T::one() / (T::one() + T::one()));
+
+        let mut g = Self::with_constant(r0);
+        let mut m = 1;
+        while m < n - half {
+            let next_m = (m * 2).min(n - half);
+            let inv_g = g.clone().inverse(next_m);
+            g = ((g + shifted.modular(next_m) * inv_g) * inv2.clone()).modular(next_m);
+            m = next_m;
+        }
+        Some(g.right_shift(half).modular(n))
+    }
+}
+
+impl<T: Field + FromNumber, C: Convolution<T>> Poly<T, C> {
+    /// Returns `g` with `g(x) = self(x + c)`.
+    pub fn taylor_shift(&self, c: T) -> Self {
+        let n = self.rank();
+        let mut fact = vec![T::one(); n + 1];
+        for i in 1..=n {
+            fact[i] = fact[i - 1] * T::from(i);
+        }
+        let inv_fact = inverse_batch(&fact[..]);
+
+        let mut b: Vec<T> = self.0.iter().zip(fact.iter()).map(|(a, f)| *a * *f).collect();
+        b.reverse();
+
+        let mut pow_c = T::one();
+        let mut d = vec![T::zero(); n + 1];
+        for i in 0..=n {
+            d[i] = pow_c * inv_fact[i];
+            pow_c = pow_c * c;
+        }
+
+        let mut r = poly_extend(C::convolution(b, d), n + 1);
+        r.reverse();
+
+        let g: Vec<T> = r.into_iter().zip(inv_fact).map(|(x, f)| x * f).collect();
+        Self::new(g)
+    }
+}
+
 impl<T: Ring + FromNumber, C: Convolution<T>> Poly<T, C> {
     pub fn new(p: Vec<T>) -> Self {
         let mut res = Self(p, PhantomData);
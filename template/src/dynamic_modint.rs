@@ -4,7 +4,10 @@ use std::{
     ops::{Add, Div, Mul, Sub}, str::FromStr, string::ParseError, num::ParseIntError,
 };
 
-use crate::{algebraic_structure::*, arithmetic::*, num_gcd::inv_mod, num_integer::Integer, num_number::Number};
+use crate::{
+    algebraic_structure::*, arithmetic::*, math::pow_mod, num_gcd::inv_mod, num_integer::Integer,
+    num_number::{FromNumber, Number}, poly::FieldSqrt,
+};
 
 pub struct Modulus<T>
 where
@@ -14,17 +17,109 @@ where
     pub zero: T,
     pub one: T,
     pub primitive_root: T,
+    /// Whether `modulus` is odd, i.e. whether the Montgomery fields below are usable.
+    /// REDC's 2-adic inverse has no meaning for an even modulus, so `DynamicModInt` falls
+    /// back to the plain `mul_mod` path in that case.
+    pub montgomery_odd: bool,
+    /// `-modulus^{-1} mod R`, where `R = 2^k` for `k` the bit width of `T`.
+    pub montgomery_n_inv: T,
+    /// `R^2 mod modulus`, used to move a plain residue into Montgomery form.
+    pub montgomery_r2: T,
 }
 
 impl<T> Modulus<T>
 where
-    T: Integer,
+    T: Integer + FromNumber,
 {
     pub fn set(&mut self, modulus: T, primitive_root: T) {
         self.modulus = modulus;
         self.primitive_root = primitive_root;
         self.zero = T::ZERO;
         self.one = T::ONE % modulus;
+        self.setup_montgomery();
+    }
+    /// Like `set`, but discovers the primitive root automatically; `modulus` must be prime.
+    pub fn set_prime(&mut self, modulus: T) {
+        self.modulus = modulus;
+        self.zero = T::ZERO;
+        self.one = T::ONE % modulus;
+        self.setup_montgomery();
+        self.primitive_root = self.discover_primitive_root();
+    }
+    /// Computes `montgomery_n_inv` and `montgomery_r2`, relying on `T`'s own wraparound at
+    /// its bit width to stand in for reduction mod `R`. The 2-adic inverse Newton's
+    /// iteration relies on only exists for odd `modulus`, so even moduli just set
+    /// `montgomery_odd = false` and leave `DynamicModInt` to fall back to `mul_mod`.
+    fn setup_montgomery(&mut self) {
+        let m = self.modulus;
+        let one = T::ONE;
+        let two = one + one;
+
+        self.montgomery_odd = m % two == one;
+        if !self.montgomery_odd {
+            return;
+        }
+
+        // R mod m = ((R - 1) mod m + 1) mod m, computed without ever materializing R
+        // itself (R = 2^k overflows T, but R - 1 is exactly T::ZERO.wrapping_sub(1)).
+        // Uses wrapping ops throughout, like `montgomery_redc`, since this is modular
+        // arithmetic at T's own bit width rather than mod `m` and must not panic under
+        // checked/debug overflow semantics.
+        let r = (T::ZERO.wrapping_sub(one) % m + one) % m;
+        self.montgomery_r2 = T::mul_mod(r, r, m);
+
+        // Newton's iteration for the 2-adic inverse of the odd integer m: each step
+        // doubles the number of correct low bits, so 6 steps (1 -> 64) covers every
+        // width T might be.
+        let mut inv = one;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(two.wrapping_sub(m.wrapping_mul(inv)));
+        }
+        self.montgomery_n_inv = T::ZERO.wrapping_sub(inv);
+    }
+    /// Finds a generator of the multiplicative group mod `self.modulus`, which must be prime.
+    ///
+    /// Skips straight to the known answer for 2 and a handful of well-known NTT-friendly
+    /// primes (the three in `conv_any_mod::NTT_PRIMES`, plus 754974721), rather than paying
+    /// for factoring `m - 1` on moduli whose primitive root is already common knowledge.
+    pub fn discover_primitive_root(&self) -> T {
+        let m = self.modulus;
+        if m == T::from(998244353u64) || m == T::from(167772161u64) || m == T::from(469762049u64)
+        {
+            return T::from(3u64);
+        }
+        if m == T::from(754974721u64) {
+            return T::from(11u64);
+        }
+        let one = T::ONE;
+        let two = one + one;
+        if m == two {
+            return one;
+        }
+        let phi = m - one;
+        let mut factors = Vec::new();
+        let mut n = phi;
+        let mut d = two;
+        while d * d <= n {
+            if n % d == T::ZERO {
+                factors.push(d);
+                while n % d == T::ZERO {
+                    n = n / d;
+                }
+            }
+            d = d + one;
+        }
+        if n > one {
+            factors.push(n);
+        }
+
+        let mut g = two;
+        loop {
+            if factors.iter().all(|&q| pow_mod(g, phi / q, m) != one) {
+                return g;
+            }
+            g = g + one;
+        }
     }
     #[inline(always)]
     pub fn add(&self, a: T, b: T) -> T {
@@ -62,10 +157,13 @@ where
     T: 'static + Integer,
 {
     fn modulus() -> &'static mut Modulus<T>;
+    /// REDC: returns `a * b * R^-1 mod modulus` for `a, b < modulus`, where `R = 2^k` for
+    /// `k` the bit width of `T`. Only meaningful when `modulus().montgomery_odd` is set.
+    fn montgomery_redc(a: T, b: T) -> T;
 }
 
 macro_rules! DynamicModulusFactoryImpl {
-    ($name: ident, $T: ty) => {
+    ($name: ident, $T: ty, $wide: ty, $bits: expr) => {
         #[derive(Clone, Copy)]
         pub struct $name;
         impl DynamicModulusFactory<$T> for $name
@@ -77,15 +175,34 @@ macro_rules! DynamicModulusFactoryImpl {
                     zero: <$T as Number>::ZERO,
                     one: <$T as Number>::ZERO,
                     primitive_root: <$T as Number>::ZERO,
+                    montgomery_odd: false,
+                    montgomery_n_inv: <$T as Number>::ZERO,
+                    montgomery_r2: <$T as Number>::ZERO,
                 };
                 unsafe { &mut singleton }
             }
+
+            #[inline(always)]
+            fn montgomery_redc(a: $T, b: $T) -> $T {
+                let modulus = Self::modulus();
+                let m = modulus.modulus;
+                // The raw, unreduced product (as large as (m - 1)^2) and the sum
+                // t + q * m are the steps that need more than T's own width.
+                let t = (a as $wide) * (b as $wide);
+                let q = (t as $T).wrapping_mul(modulus.montgomery_n_inv);
+                let u = ((t + (q as $wide) * (m as $wide)) >> $bits) as $T;
+                if u >= m {
+                    u - m
+                } else {
+                    u
+                }
+            }
         }
     }
 }
 pub (crate)use DynamicModulusFactoryImpl;
-DynamicModulusFactoryImpl!(MF32, u32);
-DynamicModulusFactoryImpl!(MF64, u64);
+DynamicModulusFactoryImpl!(MF32, u32, u64, 32);
+DynamicModulusFactoryImpl!(MF64, u64, u128, 64);
 
 pub struct DynamicModInt<T, F>
 where
@@ -157,20 +274,40 @@ where
     T: 'static + Integer,
     F: DynamicModulusFactory<T>,
 {
+    /// Takes a plain residue and stores it internally: in Montgomery form for an odd
+    /// modulus, or as-is (REDC has no 2-adic inverse to fall back on) for an even one.
     #[inline(always)]
     pub fn new(v: T) -> Self {
+        let modulus = F::modulus();
+        let v = v % modulus.modulus;
+        if modulus.montgomery_odd {
+            Self::new_montgomery(F::montgomery_redc(v, modulus.montgomery_r2))
+        } else {
+            Self::new_montgomery(v)
+        }
+    }
+    /// Wraps a value that is already in the representation `v` uses (Montgomery form
+    /// for an odd modulus, plain otherwise), with no conversion.
+    #[inline(always)]
+    fn new_montgomery(v: T) -> Self {
         Self {
             v,
             phantom: PhantomData,
         }
     }
+    /// Returns the canonical (non-Montgomery) residue.
     #[inline(always)]
     pub fn value(&self) -> T {
-        self.v
+        let modulus = F::modulus();
+        if modulus.montgomery_odd {
+            F::montgomery_redc(self.v, T::ONE)
+        } else {
+            self.v
+        }
     }
     #[inline(always)]
     pub fn possible_inv(&self) -> Option<DynamicModInt<T, F>> {
-        F::modulus().inv(self.v).map(Self::new)
+        F::modulus().inv(self.value()).map(Self::new)
     }
 }
 
@@ -180,7 +317,7 @@ where
     F: DynamicModulusFactory<T>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Display::fmt(&self.v, f)
+        Display::fmt(&self.value(), f)
     }
 }
 impl<T, F> Debug for DynamicModInt<T, F>
@@ -189,7 +326,7 @@ where
     F: DynamicModulusFactory<T>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(&self.v, f)
+        Debug::fmt(&self.value(), f)
     }
 }
 
@@ -202,7 +339,7 @@ where
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        Self::new(F::modulus().div(self.v, rhs.v))
+        Self::new(F::modulus().div(self.value(), rhs.value()))
     }
 }
 
@@ -214,7 +351,12 @@ where
     type Output = Self;
     #[inline(always)]
     fn mul(self, rhs: Self) -> Self::Output {
-        Self::new(T::mul_mod(self.v, rhs.v, F::modulus().modulus))
+        let modulus = F::modulus();
+        if modulus.montgomery_odd {
+            Self::new_montgomery(F::montgomery_redc(self.v, rhs.v))
+        } else {
+            Self::new_montgomery(modulus.mul(self.v, rhs.v))
+        }
     }
 }
 
@@ -226,7 +368,10 @@ where
     type Output = Self;
     #[inline(always)]
     fn sub(self, rhs: Self) -> Self::Output {
-        Self::new(F::modulus().sub(self.v, rhs.v))
+        // Addition/subtraction is linear, so it commutes with the Montgomery scaling by
+        // R: subtracting two Montgomery-form values directly yields a Montgomery-form
+        // result, with no REDC step needed.
+        Self::new_montgomery(F::modulus().sub(self.v, rhs.v))
     }
 }
 
@@ -238,7 +383,7 @@ where
     type Output = Self;
     #[inline(always)]
     fn add(self, rhs: Self) -> Self::Output {
-        Self::new(F::modulus().add(self.v, rhs.v))
+        Self::new_montgomery(F::modulus().add(self.v, rhs.v))
     }
 }
 
@@ -249,7 +394,7 @@ where
 {
     #[inline(always)]
     fn mul_inv(&self) -> Self {
-        Self::new(F::modulus().inv(self.v).unwrap())
+        Self::new(F::modulus().inv(self.value()).unwrap())
     }
 }
 
@@ -302,3 +447,64 @@ where
         Self::new(F::modulus().one)
     }
 }
+
+impl<T, F> FieldSqrt for DynamicModInt<T, F>
+where
+    T: 'static + Integer,
+    F: DynamicModulusFactory<T>,
+{
+    /// Tonelli-Shanks, run directly over the modulus' own integer representation.
+    fn sqrt(&self) -> Option<Self> {
+        let p = F::modulus().modulus;
+        let zero = T::ZERO;
+        let one = T::ONE;
+        let two = one + one;
+        let a = self.value() % p;
+        if a == zero {
+            return Some(Self::new(zero));
+        }
+        if p == two {
+            return Some(Self::new(a));
+        }
+        if pow_mod(a, (p - one) / two, p) != one {
+            return None;
+        }
+        let mut q = p - one;
+        let mut s = zero;
+        while q % two == zero {
+            q = q / two;
+            s = s + one;
+        }
+        if s == one {
+            return Some(Self::new(pow_mod(a, (p + one) / (two + two), p)));
+        }
+        let mut z = two;
+        while pow_mod(z, (p - one) / two, p) != p - one {
+            z = z + one;
+        }
+        let mut m = s;
+        let mut c = pow_mod(z, q, p);
+        let mut t = pow_mod(a, q, p);
+        let mut r = pow_mod(a, (q + one) / two, p);
+        while t != one {
+            let mut i = zero;
+            let mut t2i = t;
+            while t2i != one {
+                t2i = T::mul_mod(t2i, t2i, p);
+                i = i + one;
+            }
+            let mut exp = one;
+            let mut j = zero;
+            while j < m - i - one {
+                exp = exp + exp;
+                j = j + one;
+            }
+            let b = pow_mod(c, exp, p);
+            m = i;
+            c = T::mul_mod(b, b, p);
+            t = T::mul_mod(t, c, p);
+            r = T::mul_mod(r, b, p);
+        }
+        Some(Self::new(r))
+    }
+}
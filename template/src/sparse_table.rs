@@ -92,3 +92,112 @@ where
             .finish()
     }
 }
+
+///
+/// disjoint sparse table
+///
+/// Unlike [SparseTable], `f` need not be idempotent: sums, products, and
+/// gcd/matrix-style monoids are all supported, still with O(1) query time.
+///
+/// O(n\log_2n) preprocess time and space complexity
+///
+/// # Example
+///
+/// ```ignore
+///
+/// let data = vec![3, 1, 4, 2];
+/// let st = DisjointSparseTable::new(&data, |a, b| a + b);
+///
+/// assert_eq!(1, st.query(1usize, 1usize));
+/// assert_eq!(4, st.query(0usize, 1usize));
+/// assert_eq!(7, st.query(1usize, 3usize));
+/// ```
+///
+pub struct DisjointSparseTable<T>
+where
+    T: Clone + Debug,
+{
+    ///
+    /// data[i][j] folds the half of block `j >> (i + 1)` (blocks of size 2^(i+1)) on
+    /// the same side of the block's midpoint `mid` as `j`: `[j, mid)` for the left
+    /// half, `[mid, j]` for the right half
+    ///
+    data: Vec<Vec<T>>,
+    f: Box<dyn Fn(T, T) -> T>,
+}
+
+impl<T> DisjointSparseTable<T>
+where
+    T: Clone + Debug,
+{
+    pub fn new(s: &[T], f: impl Fn(T, T) -> T + 'static) -> Self {
+        let n = s.len();
+        if n == 0 {
+            return Self {
+                data: Vec::new(),
+                f: Box::new(f),
+            };
+        }
+        let level = (log2_floor(n) + 1) as usize + 1;
+        let mut data: Vec<Vec<T>> = vec![vec![s[0].clone(); n]; level];
+        data[0] = s.to_vec();
+
+        for i in 1..level {
+            let block = 1usize << (i + 1);
+            let mut lo = 0;
+            while lo < n {
+                // `mid` is the bit-aligned block midpoint, not the arithmetic midpoint of
+                // a possibly-truncated trailing block: `query`'s `(l ^ r)` level lookup
+                // assumes every index's "half" is determined by this fixed boundary, even
+                // when the trailing block is shorter than a full `block`. The trailing
+                // block can still be truncated below `mid`, in which case only the left
+                // half `[lo, min(mid, n))` exists.
+                let hi = (lo + block).min(n);
+                let mid = lo + block / 2;
+                let hi_left = mid.min(n);
+
+                if mid < hi {
+                    data[i][mid] = s[mid].clone();
+                    for j in mid + 1..hi {
+                        data[i][j] = f(data[i][j - 1].clone(), s[j].clone());
+                    }
+                }
+
+                data[i][hi_left - 1] = s[hi_left - 1].clone();
+                for j in (lo..hi_left - 1).rev() {
+                    data[i][j] = f(s[j].clone(), data[i][j + 1].clone());
+                }
+
+                lo += block;
+            }
+        }
+
+        Self {
+            data,
+            f: Box::new(f),
+        }
+    }
+
+    ///
+    /// O(1) find the fold over data[l..r] for any associative `f`
+    ///
+    pub fn query(&self, l: usize, r: usize) -> T {
+        should!(l <= r);
+        if l == r {
+            return self.data[0][l].clone();
+        }
+        let level = (usize::BITS - 1 - (l ^ r).leading_zeros()) as usize;
+        (self.f)(self.data[level][l].clone(), self.data[level][r].clone())
+    }
+}
+
+impl<T> Debug for DisjointSparseTable<T>
+where
+    T: Clone + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DisjointSparseTable")
+            .field("data", &self.data)
+            .finish()
+    }
+}